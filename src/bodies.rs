@@ -0,0 +1,212 @@
+// =============================================================================
+// BODIES: USER-DEFINED CELESTIAL POINTS LOADED AT RUNTIME
+// =============================================================================
+
+use crate::{EpicycleDims, PlanetParam, PlanetType, PLANETS};
+use std::fs;
+use std::sync::OnceLock;
+
+static CUSTOM: OnceLock<Vec<PlanetParam>> = OnceLock::new();
+
+const RESERVED: &[&str] = &["Rahu", "Ketu"];
+const NO_EPICYCLE: EpicycleDims = EpicycleDims { even: 0.0, odd: 0.0 };
+
+/// Bodies registered with `--bodies`, in file order; empty when none were given.
+pub(crate) fn custom() -> &'static [PlanetParam] {
+    CUSTOM.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+pub(crate) fn find(name: &str) -> Option<&'static PlanetParam> {
+    PLANETS.iter().chain(custom()).find(|p| p.name == name)
+}
+
+/// Loads `path` and makes its bodies visible to every mode. Call once, before
+/// any computation; names are leaked to `'static` to fit `PlanetParam`.
+pub(crate) fn register(path: &str) -> Result<(), String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path, e))?;
+    let bodies = parse(path, &raw)?;
+    CUSTOM.set(bodies).map_err(|_| "custom bodies already registered".to_string())
+}
+
+#[derive(Default)]
+struct Draft {
+    name: String,
+    line: usize,
+    model: Option<PlanetType>,
+    revs: Option<f64>,
+    offset: f64,
+    apsis_revs: Option<f64>,
+    apsis_offset: Option<f64>,
+    manda: Option<EpicycleDims>,
+    sighra: Option<EpicycleDims>,
+}
+
+fn parse_epicycle(value: &str) -> Option<EpicycleDims> {
+    let (even, odd) = value.split_once(',')?;
+    let num = |s: &str| s.trim().parse::<f64>().ok().filter(|v| v.is_finite());
+    Some(EpicycleDims { even: num(even)?, odd: num(odd)? })
+}
+
+fn finish(path: &str, d: Draft, seen: &[PlanetParam]) -> Result<PlanetParam, String> {
+    let at = format!("{}:{}: [{}]", path, d.line, d.name);
+    if d.name.is_empty() { return Err(format!("{} needs a name", at)); }
+    // Names become a column of the pipe-delimited position listing
+    if d.name.chars().any(|c| c == '|' || c.is_control()) {
+        return Err(format!("{}: names may not contain '|' or control characters", at));
+    }
+    if PLANETS.iter().chain(seen).any(|p| p.name == d.name) || RESERVED.contains(&d.name.as_str()) {
+        return Err(format!("{} is already defined", at));
+    }
+    let ptype = d.model.ok_or_else(|| format!("{} needs model = luminary|star|inferior|node", at))?;
+    let revs = d.revs.ok_or_else(|| format!("{} needs revs", at))?;
+    match ptype {
+        PlanetType::Node if d.manda.is_some() || d.sighra.is_some() => {
+            return Err(format!("{}: node bodies take no epicycles", at));
+        }
+        PlanetType::Node if d.apsis_revs.is_some() || d.apsis_offset.is_some() => {
+            return Err(format!("{}: node bodies take no apsis", at));
+        }
+        PlanetType::Luminary if d.sighra.is_some() => {
+            return Err(format!("{}: luminaries take no sighra epicycle", at));
+        }
+        // Without a sighra epicycle the body's own motion would never be used
+        PlanetType::Inferior if d.sighra.is_none() => {
+            return Err(format!("{}: inferior bodies need a sighra epicycle", at));
+        }
+        _ => {}
+    }
+    Ok(PlanetParam {
+        name: Box::leak(d.name.into_boxed_str()),
+        ptype,
+        revs,
+        manda_ep: d.manda.unwrap_or(NO_EPICYCLE),
+        sighra_ep: d.sighra,
+        bija_offset: d.offset,
+        apsis_offset: d.apsis_offset.unwrap_or(0.0),
+        apsis_revs: d.apsis_revs.unwrap_or(0.0),
+    })
+}
+
+/// INI-style: a `[Name]` header per body followed by `key = value` lines.
+/// Keys: model, revs, offset, apsis_revs, apsis_offset, manda, sighra
+/// (epicycles as `even, odd`). `#` starts a comment.
+fn parse(path: &str, raw: &str) -> Result<Vec<PlanetParam>, String> {
+    let mut out = Vec::new();
+    let mut draft: Option<Draft> = None;
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let at = format!("{}:{}", path, i + 1);
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some(d) = draft.take() { out.push(finish(path, d, &out)?); }
+            draft = Some(Draft { name: name.trim().to_string(), line: i + 1, ..Default::default() });
+            continue;
+        }
+        let d = draft.as_mut().ok_or_else(|| format!("{}: expected [Name] before settings", at))?;
+        let (key, value) = line.split_once('=').ok_or_else(|| format!("{}: expected key = value", at))?;
+        let (key, value) = (key.trim(), value.trim());
+        let num = || value.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| format!("{}: bad number '{}'", at, value));
+        let epicycle = || parse_epicycle(value).ok_or_else(|| format!("{}: expected 'even, odd', got '{}'", at, value));
+        match key {
+            "model" => d.model = Some(match value {
+                "luminary" => PlanetType::Luminary,
+                "star" => PlanetType::Star,
+                "inferior" => PlanetType::Inferior,
+                "node" => PlanetType::Node,
+                _ => return Err(format!("{}: unknown model '{}'", at, value)),
+            }),
+            "revs" => d.revs = Some(num()?),
+            "offset" => d.offset = num()?,
+            "apsis_revs" => d.apsis_revs = Some(num()?),
+            "apsis_offset" => d.apsis_offset = Some(num()?),
+            "manda" => d.manda = Some(epicycle()?),
+            "sighra" => d.sighra = Some(epicycle()?),
+            _ => return Err(format!("{}: unknown key '{}'", at, key)),
+        }
+    }
+    if let Some(d) = draft.take() { out.push(finish(path, d, &out)?); }
+    if out.is_empty() { return Err(format!("{}: no bodies", path)); }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# alternative nodes
+[Rahu SS]
+model = node
+revs = -232238
+offset = 176.10499   # matches the built-in Rahu at J2000
+
+[Venus Copy]
+model = inferior
+revs = 7011399.58589762
+manda = 12, 11
+sighra = 262, 260
+
+[Bare Star]
+model = star
+revs = 146568
+";
+
+    #[test]
+    fn parses_sections_with_defaults() {
+        let bodies = parse("b.conf", SAMPLE).unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert_eq!(bodies[0].name, "Rahu SS");
+        assert_eq!(bodies[0].ptype, PlanetType::Node);
+        assert_eq!(bodies[0].bija_offset, 176.10499);
+        assert_eq!(bodies[1].ptype, PlanetType::Inferior);
+        assert_eq!(bodies[1].sighra_ep.unwrap().odd, 260.0);
+        assert_eq!(bodies[2].manda_ep.even, 0.0);
+        assert!(bodies[2].sighra_ep.is_none());
+        assert_eq!(bodies[2].apsis_revs, 0.0);
+    }
+
+    fn err(raw: &str) -> String {
+        parse("b.conf", raw).err().expect("parse should fail")
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(err("model = node\n"), "b.conf:1: expected [Name] before settings");
+        assert_eq!(err("[X]\nmodel node\n"), "b.conf:2: expected key = value");
+        assert_eq!(err("[X]\nmodel = planet\n"), "b.conf:2: unknown model 'planet'");
+        assert_eq!(err("[X]\ncolour = red\n"), "b.conf:2: unknown key 'colour'");
+        assert_eq!(err("[X]\nmodel = node\nrevs = inf\n"), "b.conf:3: bad number 'inf'");
+        assert_eq!(err("[X]\nmodel = star\nmanda = 12\n"), "b.conf:3: expected 'even, odd', got '12'");
+        assert_eq!(err("[X]\nmodel = star\nmanda = 1, nan\n"), "b.conf:3: expected 'even, odd', got '1, nan'");
+        assert_eq!(err("# empty\n"), "b.conf: no bodies");
+    }
+
+    #[test]
+    fn rejects_incomplete_or_inconsistent_bodies() {
+        assert!(err("[X]\nrevs = 1\n").contains("needs model"));
+        assert!(err("[X]\nmodel = node\n").contains("needs revs"));
+        assert!(err("[ ]\nmodel = node\nrevs = 1\n").contains("needs a name"));
+        assert!(err("[X]\nmodel = node\nrevs = 1\nmanda = 1, 2\n").contains("node bodies take no epicycles"));
+        assert!(err("[X]\nmodel = node\nrevs = 1\napsis_revs = 99\n").contains("node bodies take no apsis"));
+        assert!(err("[X]\nmodel = node\nrevs = 1\napsis_offset = 0\n").contains("node bodies take no apsis"));
+        assert!(err("[X]\nmodel = luminary\nrevs = 1\nsighra = 1, 2\n").contains("no sighra"));
+        assert!(err("[X]\nmodel = inferior\nrevs = 1\nmanda = 1, 2\n").contains("need a sighra"));
+    }
+
+    #[test]
+    fn rejects_names_that_break_the_listing() {
+        assert!(err("[A|B]\nmodel = node\nrevs = 1\n").contains("may not contain '|'"));
+        assert!(err("[A\u{7}B]\nmodel = node\nrevs = 1\n").contains("control characters"));
+    }
+
+    #[test]
+    fn rejects_duplicate_and_reserved_names() {
+        for name in ["Sun", "Saturn", "Rahu", "Ketu"] {
+            let e = err(&format!("[{}]\nmodel = node\nrevs = 1\n", name));
+            assert_eq!(e, format!("b.conf:1: [{}] is already defined", name));
+        }
+        let e = err("[X]\nmodel = node\nrevs = 1\n[X]\nmodel = node\nrevs = 2\n");
+        assert_eq!(e, "b.conf:4: [X] is already defined");
+    }
+}
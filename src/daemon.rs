@@ -0,0 +1,381 @@
+// =============================================================================
+// DAEMON: SCHEDULED PANCHANGA FILES FOR STATIC SITES
+// =============================================================================
+
+use crate::format::{html_escape, ics_fold, ics_text, json_str, local_iso, local_time};
+use crate::locations::{self, Location};
+use crate::panchanga::{self, Element, Panchanga};
+use crate::{flag_value, has_flag, julian_day};
+use chrono::{Duration, NaiveDate, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+const DEFAULT_DAYS: i64 = 7;
+const MAX_DAYS: i64 = 366;
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+struct Config {
+    out_dir: PathBuf,
+    locations: Vec<Location>,
+    days: i64,             // Days written per location, starting with local today
+    interval_secs: u64,    // Pause between refreshes
+    once: bool,
+}
+
+fn parse_config(args: &[String]) -> Result<Config, String> {
+    let out_dir = flag_value(args, "--out-dir").ok_or("--daemon needs --out-dir <DIR>")?;
+    let days = match flag_value(args, "--days") {
+        Some(s) => s.parse().ok().filter(|&d: &i64| d > 0 && d <= MAX_DAYS)
+            .ok_or_else(|| format!("bad --days '{}' (must be 1 to {})", s, MAX_DAYS))?,
+        None => DEFAULT_DAYS,
+    };
+    let interval_secs = match flag_value(args, "--interval") {
+        Some(s) => s.parse().ok().filter(|&i: &u64| i > 0).ok_or_else(|| format!("bad --interval '{}'", s))?,
+        None => DEFAULT_INTERVAL_SECS,
+    };
+    let locations = locations::from_args(args)?;
+    check_slugs(&locations)?;
+    Ok(Config {
+        out_dir: PathBuf::from(out_dir),
+        locations,
+        days,
+        interval_secs,
+        once: has_flag(args, "--once"),
+    })
+}
+
+fn slug(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() { out.push(c); } else if !out.ends_with('-') { out.push('-'); }
+    }
+    out.trim_matches('-').to_string()
+}
+
+/// Every location needs its own non-empty directory name under the out-dir.
+fn check_slugs(locations: &[Location]) -> Result<(), String> {
+    let mut seen: Vec<(String, &str)> = Vec::new();
+    for loc in locations {
+        let s = slug(&loc.name);
+        if s.is_empty() {
+            return Err(format!("location '{}' has no letters or digits to name its directory", loc.name));
+        }
+        if let Some((_, other)) = seen.iter().find(|(seen_slug, _)| *seen_slug == s) {
+            return Err(format!("locations '{}' and '{}' would share the directory '{}'", other, loc.name, s));
+        }
+        seen.push((s, &loc.name));
+    }
+    Ok(())
+}
+
+fn clock(jd: Option<f64>, tz: f64) -> String {
+    jd.map(|t| local_time(t, tz).format("%H:%M").to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn until(e: &Element, tz: f64) -> String {
+    format!("{} until {}", e.name, local_time(e.ends, tz).format("%Y-%m-%d %H:%M"))
+}
+
+/// Label/value rows shared by the ICS description and the HTML page.
+fn summary_rows(loc: &Location, p: &Panchanga) -> Vec<(&'static str, String)> {
+    vec![
+        ("Vāra", p.vara.to_string()),
+        ("Sunrise", clock(p.sunrise, loc.tz)),
+        ("Sunset", clock(p.sunset, loc.tz)),
+        ("Tithi", format!("{} {}", p.paksha, until(&p.tithi, loc.tz))),
+        ("Nakṣatra", until(&p.nakshatra, loc.tz)),
+        ("Yoga", until(&p.yoga, loc.tz)),
+        ("Karaṇa", until(&p.karana, loc.tz)),
+    ]
+}
+
+fn element_json(e: &Element, tz: f64) -> String {
+    format!("{{\"index\":{},\"name\":{},\"ends\":{}}}", e.index, json_str(e.name), json_str(&local_iso(e.ends, tz)))
+}
+
+fn render_json(loc: &Location, p: &Panchanga) -> String {
+    let time = |jd: Option<f64>| jd.map(|t| json_str(&local_iso(t, loc.tz))).unwrap_or_else(|| "null".to_string());
+    let positions: Vec<String> = p.positions.iter()
+        .map(|(name, lon)| format!("{{\"body\":{},\"longitude\":{:.6}}}", json_str(name), lon))
+        .collect();
+    format!(
+        "{{\"location\":{{\"name\":{},\"lat\":{},\"lon\":{},\"tz\":{}}},\"date\":{},\"vara\":{},\
+         \"sunrise\":{},\"sunset\":{},\"paksha\":{},\"tithi\":{},\"nakshatra\":{},\"yoga\":{},\"karana\":{},\
+         \"positions\":[{}]}}\n",
+        json_str(&loc.name), loc.lat, loc.lon, loc.tz, json_str(&p.date.to_string()), json_str(p.vara),
+        time(p.sunrise), time(p.sunset), json_str(p.paksha), element_json(&p.tithi, loc.tz),
+        element_json(&p.nakshatra, loc.tz), element_json(&p.yoga, loc.tz), element_json(&p.karana, loc.tz),
+        positions.join(","),
+    )
+}
+
+fn render_ics(loc: &Location, p: &Panchanga) -> String {
+    let day = p.date.format("%Y%m%d");
+    let next = (p.date + Duration::days(1)).format("%Y%m%d");
+    let description: Vec<String> = summary_rows(loc, p).iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+    let lines = [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//surya_sidhanta//panchanga//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@surya_sidhanta", day, slug(&loc.name)),
+        format!("DTSTAMP:{}T000000Z", day),   // Deterministic so unchanged days are not rewritten
+        format!("DTSTART;VALUE=DATE:{}", day),
+        format!("DTEND;VALUE=DATE:{}", next),
+        format!("SUMMARY:{}", ics_text(&format!("{} {} · {}", p.paksha, p.tithi.name, p.nakshatra.name))),
+        format!("DESCRIPTION:{}", ics_text(&description.join("\n"))),
+        format!("LOCATION:{}", ics_text(&loc.name)),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+    lines.iter().map(|l| ics_fold(l)).collect()
+}
+
+fn render_html(loc: &Location, p: &Panchanga) -> String {
+    let title = format!("Pañcāṅga — {} — {}", loc.name, p.date);
+    let rows: String = summary_rows(loc, p).iter()
+        .map(|(k, v)| format!("<tr><th>{}</th><td>{}</td></tr>\n", k, html_escape(v)))
+        .collect();
+    let positions: String = p.positions.iter()
+        .map(|(name, lon)| format!("<tr><th>{}</th><td>{:.4}°</td></tr>\n", html_escape(name), lon))
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"sa-Latn\">\n<head>\n<meta charset=\"utf-8\">\n<title>{t}</title>\n</head>\n<body>\n\
+         <h1>{t}</h1>\n<table>\n{rows}</table>\n<h2>Sidereal longitudes at sunrise</h2>\n<table>\n{positions}</table>\n\
+         </body>\n</html>\n",
+        t = html_escape(&title), rows = rows, positions = positions,
+    )
+}
+
+/// Writes via a temporary file and rename so readers never see a partial file.
+/// Returns false when the file already holds `body`.
+fn write_if_changed(path: &Path, body: &str) -> Result<bool, String> {
+    if fs::read(path).map(|old| old == body.as_bytes()).unwrap_or(false) {
+        return Ok(false);
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, body).map_err(|e| format!("writing {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("renaming {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+type Renderer = fn(&Location, &Panchanga) -> String;
+const OUTPUTS: &[(&str, Renderer)] = &[("json", render_json), ("ics", render_ics), ("html", render_html)];
+
+/// One pass over every location; errors are logged so a bad path does not
+/// stop the others. Returns (written, unchanged, failed) file counts.
+fn refresh(cfg: &Config) -> (usize, usize, usize) {
+    let now = julian_day(Utc::now().naive_utc());
+    let (mut written, mut unchanged, mut failed) = (0, 0, 0);
+    for loc in &cfg.locations {
+        let dir = cfg.out_dir.join(slug(&loc.name));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("[daemon] creating {}: {}", dir.display(), e);
+            failed += OUTPUTS.len() * cfg.days as usize;
+            continue;
+        }
+        let today: NaiveDate = local_time(now, loc.tz).date();
+        for offset in 0..cfg.days {
+            let date = today + Duration::days(offset);
+            let p = panchanga::compute(date, loc);
+            for (ext, render) in OUTPUTS {
+                let path = dir.join(format!("{}.{}", date, ext));
+                match write_if_changed(&path, &render(loc, &p)) {
+                    Ok(true) => written += 1,
+                    Ok(false) => unchanged += 1,
+                    Err(e) => { eprintln!("[daemon] {}", e); failed += 1; }
+                }
+            }
+        }
+    }
+    (written, unchanged, failed)
+}
+
+/// `--daemon --out-dir DIR [--cities FILE] [--days N] [--interval SECS] [--once]`:
+/// writes DIR/<city>/<YYYY-MM-DD>.{json,ics,html} and refreshes them every
+/// interval, rolling forward as local dates change. With `--once`, any file
+/// that fails to write makes the run an error so cron sees it.
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let cfg = parse_config(args)?;
+    fs::create_dir_all(&cfg.out_dir).map_err(|e| format!("creating {}: {}", cfg.out_dir.display(), e))?;
+    loop {
+        let (written, unchanged, failed) = refresh(&cfg);
+        eprintln!(
+            "[daemon] {} written, {} unchanged, {} failed across {} locations",
+            written, unchanged, failed, cfg.locations.len(),
+        );
+        if cfg.once {
+            return if failed > 0 { Err(format!("{} files failed to write", failed)) } else { Ok(()) };
+        }
+        thread::sleep(std::time::Duration::from_secs(cfg.interval_secs));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn loc(name: &str) -> Location {
+        Location { name: name.to_string(), lat: 0.0, lon: 0.0, tz: 0.0 }
+    }
+
+    #[test]
+    fn slug_lowercases_and_collapses_separators() {
+        assert_eq!(slug("New York"), "new-york");
+        assert_eq!(slug("  St. John's, NL "), "st-john-s-nl");
+        assert_eq!(slug("Thiruvananthapuram"), "thiruvananthapuram");
+        assert_eq!(slug("Śrīnagar"), "śrīnagar");
+        assert_eq!(slug("***"), "");
+    }
+
+    #[test]
+    fn rejects_empty_and_colliding_slugs() {
+        assert!(check_slugs(&[loc("Ujjain"), loc("New York")]).is_ok());
+        assert!(check_slugs(&[loc("***")]).unwrap_err().contains("no letters or digits"));
+        let err = check_slugs(&[loc("New York"), loc("new york")]).unwrap_err();
+        assert!(err.contains("'New York' and 'new york'") && err.contains("new-york"), "{}", err);
+    }
+
+    #[test]
+    fn days_must_be_within_limit() {
+        let base = ["surya_sidhanta", "--daemon", "--out-dir", "/tmp/x", "--days"];
+        for bad in ["0", "-3", "367", "100000000000", "seven"] {
+            let list: Vec<&str> = base.iter().copied().chain([bad]).collect();
+            assert!(parse_config(&args(&list)).is_err(), "{}", bad);
+        }
+        let list: Vec<&str> = base.iter().copied().chain(["366"]).collect();
+        assert_eq!(parse_config(&args(&list)).unwrap().days, 366);
+    }
+
+    /// Minimal JSON reader for the tests: returns the keys of the top-level
+    /// object, failing on anything that is not well-formed JSON.
+    fn json_keys(text: &str) -> Result<Vec<String>, String> {
+        struct P<'a> { s: &'a [u8], i: usize }
+        impl P<'_> {
+            fn ws(&mut self) { while self.i < self.s.len() && self.s[self.i].is_ascii_whitespace() { self.i += 1; } }
+            fn eat(&mut self, c: u8) -> Result<(), String> {
+                self.ws();
+                if self.s.get(self.i) == Some(&c) { self.i += 1; Ok(()) } else { Err(format!("expected '{}' at {}", c as char, self.i)) }
+            }
+            fn string(&mut self) -> Result<String, String> {
+                self.eat(b'"')?;
+                let start = self.i;
+                while let Some(&c) = self.s.get(self.i) {
+                    match c {
+                        b'"' => { self.i += 1; return Ok(String::from_utf8_lossy(&self.s[start..self.i - 1]).into_owned()); }
+                        b'\\' => self.i += 2,
+                        c if c < 0x20 => return Err(format!("raw control character at {}", self.i)),
+                        _ => self.i += 1,
+                    }
+                }
+                Err("unterminated string".to_string())
+            }
+            fn value(&mut self) -> Result<Vec<String>, String> {
+                self.ws();
+                match self.s.get(self.i) {
+                    Some(b'{') => {
+                        self.i += 1;
+                        let mut keys = Vec::new();
+                        self.ws();
+                        if self.s.get(self.i) == Some(&b'}') { self.i += 1; return Ok(keys); }
+                        loop {
+                            keys.push(self.string()?);
+                            self.eat(b':')?;
+                            self.value()?;
+                            self.ws();
+                            if self.s.get(self.i) == Some(&b',') { self.i += 1; } else { self.eat(b'}')?; return Ok(keys); }
+                        }
+                    }
+                    Some(b'[') => {
+                        self.i += 1;
+                        self.ws();
+                        if self.s.get(self.i) == Some(&b']') { self.i += 1; return Ok(Vec::new()); }
+                        loop {
+                            self.value()?;
+                            self.ws();
+                            if self.s.get(self.i) == Some(&b',') { self.i += 1; } else { self.eat(b']')?; return Ok(Vec::new()); }
+                        }
+                    }
+                    Some(b'"') => self.string().map(|_| Vec::new()),
+                    _ => {
+                        let start = self.i;
+                        while self.i < self.s.len() && b"+-.eE0123456789truefalsn".contains(&self.s[self.i]) { self.i += 1; }
+                        let word = std::str::from_utf8(&self.s[start..self.i]).unwrap_or("");
+                        if word == "true" || word == "false" || word == "null" || word.parse::<f64>().is_ok_and(f64::is_finite) {
+                            Ok(Vec::new())
+                        } else {
+                            Err(format!("bad literal '{}' at {}", word, start))
+                        }
+                    }
+                }
+            }
+        }
+        let mut p = P { s: text.as_bytes(), i: 0 };
+        let keys = p.value()?;
+        p.ws();
+        if p.i != p.s.len() { return Err(format!("trailing data at {}", p.i)); }
+        Ok(keys)
+    }
+
+    #[test]
+    fn refresh_writes_once_then_leaves_files_unchanged() {
+        let out_dir = std::env::temp_dir().join(format!("surya_daemon_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&out_dir);
+        let cfg = Config {
+            out_dir: out_dir.clone(),
+            locations: vec![Location { name: "Ujjain".to_string(), lat: 23.1765, lon: 75.7885, tz: 5.5 }],
+            days: 2,
+            interval_secs: 1,
+            once: true,
+        };
+        assert_eq!(refresh(&cfg), (6, 0, 0));
+        assert_eq!(refresh(&cfg), (0, 6, 0));
+
+        let dir = out_dir.join("ujjain");
+        let mut names: Vec<String> = fs::read_dir(&dir).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 6, "{:?}", names);
+        assert!(names.iter().all(|n| !n.ends_with(".tmp")));
+
+        let json_name = names.iter().find(|n| n.ends_with(".json")).unwrap();
+        let json = fs::read_to_string(dir.join(json_name)).unwrap();
+        let keys = json_keys(&json).unwrap();
+        assert_eq!(keys, [
+            "location", "date", "vara", "sunrise", "sunset", "paksha",
+            "tithi", "nakshatra", "yoga", "karana", "positions",
+        ]);
+        assert!(json.contains(&format!("\"date\":\"{}\"", json_name.trim_end_matches(".json"))));
+
+        let ics = fs::read_to_string(dir.join(json_name.replace(".json", ".ics"))).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n") && ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+        let html = fs::read_to_string(dir.join(json_name.replace(".json", ".html"))).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>") && html.contains("<th>Tithi</th>"));
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    #[test]
+    fn json_reader_rejects_malformed_text() {
+        assert!(json_keys("{\"a\":1,}").is_err());
+        assert!(json_keys("{\"a\":NaN}").is_err());
+        assert_eq!(json_keys("{\"a\":[1,\"x\",null],\"b\":{}}").unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn out_dir_is_required() {
+        let err = parse_config(&args(&["surya_sidhanta", "--daemon"])).err().unwrap();
+        assert_eq!(err, "--daemon needs --out-dir <DIR>");
+    }
+}
@@ -0,0 +1,346 @@
+// =============================================================================
+// ECLIPSES: SEARCH AND LOCAL CIRCUMSTANCES (SS IV-V)
+// =============================================================================
+
+use crate::bodies;
+use crate::format::{json_str, local_iso, local_time, offset_str};
+use crate::horizon::{altitude, separation, to_equatorial, topocentric};
+use crate::locations::{self, Location};
+use crate::{
+    calculate_node_longitude, calculate_true_position, daily_motion, flag_value, from_julian_day,
+    get_mean_sun, get_true_sun_moon, has_flag, julian_day, parse_datetime, positional_args, sin_d, wrap180,
+    PlanetParam, PlanetType, JD_KALI_EPOCH, PLANETS,
+};
+
+const MOON_MAX_LAT: f64 = 270.0 / 60.0;   // Vikṣepa of the Moon, 4°30' (SS I.68)
+const SUN_DIAMETER: f64 = 6500.0;         // Yojanas (SS IV.1)
+const MOON_DIAMETER: f64 = 480.0;
+const EARTH_DIAMETER: f64 = 1600.0;       // (SS I.59)
+const SUN_ORBIT: f64 = 4_331_500.0;       // Yojanas (SS XII.85-87)
+const MOON_ORBIT: f64 = 324_000.0;
+const YOJANAS_PER_MINUTE: f64 = 15.0;     // At the Moon's orbit (SS IV.3)
+
+const SEARCH_LUNATIONS: usize = 60;
+const HALF_WINDOW: f64 = 0.25;            // Days either side of syzygy scanned for contacts
+const STEP: f64 = 1.0 / 1440.0;           // One minute
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EclipseKind { Lunar, Solar }
+
+impl EclipseKind {
+    fn label(self) -> &'static str {
+        match self { EclipseKind::Lunar => "lunar", EclipseKind::Solar => "solar" }
+    }
+}
+
+/// `node` replaces Rahu with a user-defined node when set (see bodies.rs).
+#[derive(Clone, Copy)]
+pub(crate) struct Eclipse { pub kind: EclipseKind, pub jd: f64, pub node: Option<&'static PlanetParam> }
+
+/// True positions and apparent sizes at one instant, all in degrees.
+struct Sky {
+    sun: f64,
+    moon: f64,
+    moon_lat: f64,
+    sun_r: f64,
+    moon_r: f64,
+    shadow_r: f64,
+    sun_par: f64,
+    moon_par: f64,
+}
+
+fn node_longitude(days: f64, node: Option<&PlanetParam>) -> f64 {
+    match node {
+        Some(p) => calculate_true_position(days, p, get_mean_sun(days)),
+        None => calculate_node_longitude(days),
+    }
+}
+
+fn sky_at(jd: f64, node: Option<&PlanetParam>) -> Sky {
+    let days = jd - JD_KALI_EPOCH;
+    let (sun, moon) = get_true_sun_moon(days);
+    let (sun_a, moon_a) = get_true_sun_moon(days - 0.5);
+    let (sun_b, moon_b) = get_true_sun_moon(days + 0.5);
+    let sun_ratio = wrap180(sun_b - sun_a) / daily_motion(PLANETS[0].revs);
+    let moon_ratio = wrap180(moon_b - moon_a) / daily_motion(PLANETS[1].revs);
+
+    // Diameters in yojanas, corrected by true/mean motion (SS IV.1-5)
+    let sun_d = SUN_DIAMETER * sun_ratio;
+    let moon_d = MOON_DIAMETER * moon_ratio;
+    let suci = EARTH_DIAMETER * moon_ratio;
+    let shadow_d = suci - (sun_d - EARTH_DIAMETER) * moon_d / sun_d;
+    let to_deg = |yojanas: f64| yojanas / YOJANAS_PER_MINUTE / 60.0;
+
+    // Horizontal parallax: one fifteenth of the true daily motion
+    Sky {
+        sun,
+        moon,
+        moon_lat: MOON_MAX_LAT * sin_d(moon - node_longitude(days, node)),
+        sun_r: to_deg(sun_d * MOON_ORBIT / SUN_ORBIT) / 2.0,
+        moon_r: to_deg(moon_d) / 2.0,
+        shadow_r: to_deg(shadow_d) / 2.0,
+        sun_par: wrap180(sun_b - sun_a) / 15.0,
+        moon_par: wrap180(moon_b - moon_a) / 15.0,
+    }
+}
+
+/// Distance between the covering and eclipsed discs, relative to first contact;
+/// negative while any eclipse is in progress for the Earth as a whole.
+fn geocentric_gap(kind: EclipseKind, jd: f64, node: Option<&PlanetParam>) -> f64 {
+    let s = sky_at(jd, node);
+    let target = if kind == EclipseKind::Lunar { s.sun + 180.0 } else { s.sun };
+    let d_lon = wrap180(s.moon - target) * s.moon_lat.to_radians().cos();
+    let sep = d_lon.hypot(s.moon_lat);
+    match kind {
+        EclipseKind::Lunar => sep - (s.shadow_r + s.moon_r),
+        EclipseKind::Solar => sep - (s.sun_r + s.moon_r + s.moon_par - s.sun_par),
+    }
+}
+
+fn elongation(kind: EclipseKind, jd: f64) -> f64 {
+    let (sun, moon) = get_true_sun_moon(jd - JD_KALI_EPOCH);
+    let target = if kind == EclipseKind::Lunar { sun + 180.0 } else { sun };
+    wrap180(moon - target)
+}
+
+fn bisect<F: Fn(f64) -> f64>(f: F, mut lo: f64, mut hi: f64) -> f64 {
+    let f_lo = f(lo);
+    for _ in 0..40 {
+        let mid = (lo + hi) / 2.0;
+        if (f(mid) < 0.0) == (f_lo < 0.0) { lo = mid; } else { hi = mid; }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Next syzygy after `from_jd` at which the Moon is close enough to a node to
+/// eclipse, restricted to `kind` when given.
+pub(crate) fn find_next(from_jd: f64, kind: Option<EclipseKind>, node: Option<&'static PlanetParam>) -> Option<Eclipse> {
+    let kinds: Vec<EclipseKind> = match kind {
+        Some(k) => vec![k],
+        None => vec![EclipseKind::Lunar, EclipseKind::Solar],
+    };
+    let mut jd = from_jd;
+    let end = from_jd + SEARCH_LUNATIONS as f64 * 29.6;
+    while jd < end {
+        for &k in &kinds {
+            let (a, b) = (elongation(k, jd), elongation(k, jd + 1.0));
+            if a < 0.0 && b >= 0.0 && b - a < 30.0 {
+                let syzygy = bisect(|t| elongation(k, t), jd, jd + 1.0);
+                let gap = (-4..=4).map(|h| geocentric_gap(k, syzygy + h as f64 / 24.0, node)).fold(f64::MAX, f64::min);
+                if gap < 0.0 { return Some(Eclipse { kind: k, jd: syzygy, node }); }
+            }
+        }
+        jd += 1.0;
+    }
+    None
+}
+
+/// Separation of the covering disc (shadow or Moon) from the eclipsed body,
+/// both radii, and the eclipsed body's altitude, as seen from `loc`.
+struct Phase { sep: f64, r_body: f64, r_cover: f64, alt: f64 }
+
+impl Phase {
+    fn outer(&self) -> f64 { self.sep - (self.r_body + self.r_cover) }
+    fn inner(&self) -> f64 { self.sep - (self.r_cover - self.r_body).abs() }
+    fn magnitude(&self) -> f64 { (self.r_body + self.r_cover - self.sep) / (2.0 * self.r_body) }
+}
+
+fn phase_at(eclipse: Eclipse, jd: f64, loc: &Location) -> Phase {
+    let s = sky_at(jd, eclipse.node);
+    let moon = to_equatorial(s.moon, s.moon_lat, jd);
+    match eclipse.kind {
+        EclipseKind::Lunar => {
+            let shadow = to_equatorial(s.sun + 180.0, 0.0, jd);
+            Phase { sep: separation(moon, shadow), r_body: s.moon_r, r_cover: s.shadow_r, alt: altitude(moon, jd, loc) }
+        }
+        EclipseKind::Solar => {
+            let sun = topocentric(to_equatorial(s.sun, 0.0, jd), s.sun_par, jd, loc);
+            let moon = topocentric(moon, s.moon_par, jd, loc);
+            Phase { sep: separation(moon, sun), r_body: s.sun_r, r_cover: s.moon_r, alt: altitude(sun, jd, loc) }
+        }
+    }
+}
+
+/// Contact times (Julian days, UTC) and visibility for one location.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Circumstances {
+    pub visible: bool,
+    pub magnitude: f64,            // Greatest magnitude with the body above the horizon
+    pub altitude: f64,             // Of the eclipsed body at `greatest`
+    pub kind_label: &'static str,  // partial / total / annular / none
+    pub first: Option<f64>,        // Sparśa
+    pub inner_begin: Option<f64>,  // Sammīlana (totality or annularity begins)
+    pub greatest: Option<f64>,     // Madhya; the greatest visible phase if madhya is below the horizon
+    pub inner_end: Option<f64>,    // Unmīlana
+    pub last: Option<f64>,         // Mokṣa
+}
+
+pub(crate) fn local_circumstances(eclipse: Eclipse, loc: &Location) -> Circumstances {
+    let kind = eclipse.kind;
+    let count = (2.0 * HALF_WINDOW / STEP).round() as usize;
+    let times: Vec<f64> = (0..=count).map(|i| eclipse.jd - HALF_WINDOW + i as f64 * STEP).collect();
+    let phases: Vec<Phase> = times.iter().map(|&t| phase_at(eclipse, t, loc)).collect();
+
+    let mut c = Circumstances { kind_label: "none", ..Default::default() };
+    let mut best_sep = f64::MAX;
+    let mut inner_cover = 0.0;
+    let mut visible_greatest = None;
+    for (i, p) in phases.iter().enumerate() {
+        if p.outer() < 0.0 && p.sep < best_sep {
+            best_sep = p.sep;
+            c.greatest = Some(times[i]);
+            c.altitude = p.alt;
+            inner_cover = p.r_cover - p.r_body;
+        }
+        if p.outer() < 0.0 && p.alt > 0.0 && p.magnitude() > c.magnitude {
+            c.magnitude = p.magnitude();
+            c.visible = true;
+            visible_greatest = Some((times[i], p.alt));
+        }
+        if i == 0 { continue; }
+        let prev = &phases[i - 1];
+        let (lo, hi) = (times[i - 1], times[i]);
+        if (prev.outer() < 0.0) != (p.outer() < 0.0) {
+            let t = bisect(|t| phase_at(eclipse, t, loc).outer(), lo, hi);
+            if p.outer() < 0.0 { c.first = Some(t); } else { c.last = Some(t); }
+        }
+        if (prev.inner() < 0.0) != (p.inner() < 0.0) {
+            let t = bisect(|t| phase_at(eclipse, t, loc).inner(), lo, hi);
+            if p.inner() < 0.0 { c.inner_begin = Some(t); } else { c.inner_end = Some(t); }
+        }
+    }
+
+    // A solar eclipse on the night side has no local meaning; lunar contacts
+    // are the same everywhere and are kept for moonrise/moonset cases.
+    if kind == EclipseKind::Solar && !c.visible {
+        return Circumstances { kind_label: "none", ..Default::default() };
+    }
+    // Publishers print the greatest time, so it must be one the observer can see
+    if let Some((t, alt)) = visible_greatest && c.altitude <= 0.0 {
+        c.greatest = Some(t);
+        c.altitude = alt;
+    }
+    if c.greatest.is_some() {
+        c.kind_label = match (c.inner_begin.is_some() || c.inner_end.is_some(), kind) {
+            (false, _) => "partial",
+            (true, EclipseKind::Solar) if inner_cover < 0.0 => "annular",
+            (true, _) => "total",
+        };
+    }
+    c
+}
+
+fn time_cell(jd: Option<f64>, tz: f64) -> String {
+    jd.map(|t| local_time(t, tz).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn time_json(jd: Option<f64>, tz: f64) -> String {
+    jd.map(|t| json_str(&local_iso(t, tz))).unwrap_or_else(|| "null".to_string())
+}
+
+fn print_table(eclipse: Eclipse, rows: &[(Location, Circumstances)]) {
+    println!("# {} eclipse, syzygy {} UTC", eclipse.kind.label(), from_julian_day(eclipse.jd).format("%Y-%m-%d %H:%M"));
+    println!("City|UTC|Type|Visible|Magnitude|Begin|InnerBegin|Greatest|InnerEnd|End|Altitude");
+    for (loc, c) in rows {
+        println!(
+            "{}|{}|{}|{}|{:.3}|{}|{}|{}|{}|{}|{:.1}",
+            loc.name, offset_str(loc.tz), c.kind_label, if c.visible { "yes" } else { "no" }, c.magnitude,
+            time_cell(c.first, loc.tz), time_cell(c.inner_begin, loc.tz), time_cell(c.greatest, loc.tz),
+            time_cell(c.inner_end, loc.tz), time_cell(c.last, loc.tz), c.altitude,
+        );
+    }
+}
+
+fn print_json(eclipse: Eclipse, rows: &[(Location, Circumstances)]) {
+    let cities: Vec<String> = rows.iter().map(|(loc, c)| {
+        format!(
+            "{{\"name\":{},\"lat\":{},\"lon\":{},\"tz\":{},\"type\":{},\"visible\":{},\"magnitude\":{:.4},\"altitude\":{:.2},\
+             \"begin\":{},\"inner_begin\":{},\"greatest\":{},\"inner_end\":{},\"end\":{}}}",
+            json_str(&loc.name), loc.lat, loc.lon, loc.tz, json_str(c.kind_label), c.visible, c.magnitude, c.altitude,
+            time_json(c.first, loc.tz), time_json(c.inner_begin, loc.tz), time_json(c.greatest, loc.tz),
+            time_json(c.inner_end, loc.tz), time_json(c.last, loc.tz),
+        )
+    }).collect();
+    println!(
+        "{{\"kind\":{},\"syzygy_utc\":{},\"cities\":[{}]}}",
+        json_str(eclipse.kind.label()),
+        json_str(&from_julian_day(eclipse.jd).format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+        cities.join(","),
+    );
+}
+
+/// `--eclipse [DATETIME] [--kind lunar|solar] [--node NAME] [--cities FILE] [--json]`:
+/// finds the next eclipse after DATETIME (default: now) and sweeps the city list.
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let from = match positional_args(args).as_slice() {
+        [] => chrono::Utc::now().naive_utc(),
+        [s] => parse_datetime(s).ok_or_else(|| format!("bad datetime '{}'", s))?,
+        [_, extra, ..] => return Err(format!("unexpected argument '{}'", extra)),
+    };
+    let kind = match flag_value(args, "--kind") {
+        Some("lunar") => Some(EclipseKind::Lunar),
+        Some("solar") => Some(EclipseKind::Solar),
+        Some(other) => return Err(format!("unknown eclipse kind '{}'", other)),
+        None => None,
+    };
+    let node = match flag_value(args, "--node") {
+        Some("Rahu") => None,
+        Some(name) => match bodies::find(name) {
+            Some(p) if p.ptype == PlanetType::Node => Some(p),
+            Some(_) => return Err(format!("'{}' is not a node body", name)),
+            None => return Err(format!("'{}' is not a user-defined node", name)),
+        },
+        None => None,
+    };
+    let cities = locations::from_args(args)?;
+
+    let eclipse = find_next(julian_day(from), kind, node)
+        .ok_or_else(|| format!("no eclipse within {} lunations", SEARCH_LUNATIONS))?;
+    let rows: Vec<(Location, Circumstances)> = cities.into_iter()
+        .map(|loc| { let c = local_circumstances(eclipse, &loc); (loc, c) })
+        .collect();
+
+    if has_flag(args, "--json") { print_json(eclipse, &rows); } else { print_table(eclipse, &rows); }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ujjain() -> Location {
+        Location { name: "Ujjain".to_string(), lat: 23.1765, lon: 75.7885, tz: 5.5 }
+    }
+
+    fn hm(jd: Option<f64>, tz: f64) -> String {
+        local_time(jd.expect("contact"), tz).format("%H:%M").to_string()
+    }
+
+    #[test]
+    fn total_lunar_eclipse_2025_09_07_at_ujjain() {
+        let from = julian_day(parse_datetime("2025-09-01T00:00:00").unwrap());
+        let eclipse = find_next(from, None, None).expect("eclipse");
+        assert_eq!(eclipse.kind, EclipseKind::Lunar);
+
+        let loc = ujjain();
+        let c = local_circumstances(eclipse, &loc);
+        assert_eq!(c.kind_label, "total");
+        assert!(c.visible);
+        assert!((c.magnitude - 1.36).abs() < 0.01, "{}", c.magnitude);
+        let contacts: Vec<String> = [c.first, c.inner_begin, c.greatest, c.inner_end, c.last]
+            .iter().map(|&t| hm(t, loc.tz)).collect();
+        assert_eq!(contacts, ["22:01", "23:08", "23:49", "00:31", "01:38"]);
+    }
+
+    #[test]
+    fn greatest_is_reported_above_the_horizon() {
+        let from = julian_day(parse_datetime("2027-07-25T00:00:00").unwrap());
+        let eclipse = find_next(from, Some(EclipseKind::Solar), None).expect("eclipse");
+        let loc = Location { name: "New York".to_string(), lat: 40.7128, lon: -74.0060, tz: -5.0 };
+        let c = local_circumstances(eclipse, &loc);
+        // Partial at sunrise: the geocentric madhya falls before the Sun is up
+        assert!(c.visible);
+        assert!(c.altitude > 0.0, "greatest at altitude {}", c.altitude);
+        assert!(c.greatest.unwrap() > c.first.unwrap() && c.greatest.unwrap() <= c.last.unwrap());
+    }
+}
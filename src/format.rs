@@ -0,0 +1,105 @@
+// =============================================================================
+// FORMAT: LOCAL TIME, JSON, HTML AND ICS HELPERS
+// =============================================================================
+
+use crate::from_julian_day;
+use chrono::{Duration, NaiveDateTime};
+
+pub(crate) fn local_time(jd: f64, tz: f64) -> NaiveDateTime {
+    from_julian_day(jd) + Duration::seconds((tz * 3600.0).round() as i64)
+}
+
+/// UTC offset as `+05:30`.
+pub(crate) fn offset_str(tz: f64) -> String {
+    let mins = (tz * 60.0).round() as i64;
+    let sign = if mins < 0 { '-' } else { '+' };
+    format!("{}{:02}:{:02}", sign, mins.abs() / 60, mins.abs() % 60)
+}
+
+/// ISO-8601 local time with offset, e.g. `2025-09-07T23:41:00+05:30`.
+pub(crate) fn local_iso(jd: f64, tz: f64) -> String {
+    format!("{}{}", local_time(jd, tz).format("%Y-%m-%dT%H:%M:%S"), offset_str(tz))
+}
+
+pub(crate) fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Escapes an iCalendar TEXT value (RFC 5545 §3.3.11).
+pub(crate) fn ics_text(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets without splitting a UTF-8 sequence.
+pub(crate) fn ics_fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in line.chars() {
+        if used + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            used = 1;
+        }
+        out.push(c);
+        used += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_str_escapes_quotes_backslashes_and_controls() {
+        assert_eq!(json_str("Ujjain"), "\"Ujjain\"");
+        assert_eq!(json_str("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_str("x\ny\t\u{1}"), "\"x\\ny\\t\\u0001\"");
+        assert_eq!(json_str("Pañcāṅga"), "\"Pañcāṅga\"");
+    }
+
+    #[test]
+    fn ics_text_escapes_separators() {
+        assert_eq!(ics_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn ics_fold_splits_at_75_octets_on_char_boundaries() {
+        let line = format!("SUMMARY:{}", "ā".repeat(60));   // 8 + 120 octets
+        let folded = ics_fold(&line);
+        assert!(folded.ends_with("\r\n"));
+        let parts: Vec<&str> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|p| p.len() <= 75), "{:?}", parts);
+        assert_eq!(parts[0].len(), 74);   // 75 would split an `ā`
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(format!("{}{}", parts[0], &parts[1][1..]), line);
+        assert_eq!(ics_fold("VERSION:2.0"), "VERSION:2.0\r\n");
+    }
+
+    #[test]
+    fn offset_str_handles_fractional_and_negative_offsets() {
+        assert_eq!(offset_str(5.5), "+05:30");
+        assert_eq!(offset_str(5.75), "+05:45");
+        assert_eq!(offset_str(-3.5), "-03:30");
+        assert_eq!(offset_str(0.0), "+00:00");
+    }
+}
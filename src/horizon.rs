@@ -0,0 +1,60 @@
+// =============================================================================
+// HORIZON: SIDEREAL LONGITUDES -> LOCAL SKY
+// =============================================================================
+
+use crate::locations::Location;
+use crate::{asin_d, atan2_d, cos_d, norm360, sin_d};
+
+const OBLIQUITY: f64 = 24.0;                 // Paramakrānti (SS II.28)
+const AYANAMSA_J2000: f64 = 23.853;          // Lahiri, the frame the engine is calibrated in
+const PRECESSION_PER_DAY: f64 = 50.29 / 3600.0 / 365.25;
+const JD_J2000: f64 = 2_451_545.0;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Equatorial { pub ra: f64, pub dec: f64 }
+
+pub(crate) fn ayanamsa(jd: f64) -> f64 {
+    AYANAMSA_J2000 + (jd - JD_J2000) * PRECESSION_PER_DAY
+}
+
+/// Sidereal ecliptic (longitude, latitude) to equatorial (RA, Dec) of date.
+pub(crate) fn to_equatorial(sid_lon: f64, lat: f64, jd: f64) -> Equatorial {
+    let lon = norm360(sid_lon + ayanamsa(jd));
+    let y = sin_d(lon) * cos_d(OBLIQUITY) - (lat.to_radians().tan() * sin_d(OBLIQUITY));
+    let ra = norm360(atan2_d(y, cos_d(lon)));
+    let dec = asin_d(sin_d(lat) * cos_d(OBLIQUITY) + cos_d(lat) * sin_d(OBLIQUITY) * sin_d(lon));
+    Equatorial { ra, dec }
+}
+
+fn local_sidereal_time(jd: f64, lon_east: f64) -> f64 {
+    let d = jd - JD_J2000;
+    let t = d / 36525.0;
+    norm360(280.46061837 + 360.98564736629 * d + 0.000387933 * t * t + lon_east)
+}
+
+fn hour_angle(eq: Equatorial, jd: f64, loc: &Location) -> f64 {
+    norm360(local_sidereal_time(jd, loc.lon) - eq.ra)
+}
+
+pub(crate) fn altitude(eq: Equatorial, jd: f64, loc: &Location) -> f64 {
+    let h = hour_angle(eq, jd, loc);
+    asin_d(sin_d(loc.lat) * sin_d(eq.dec) + cos_d(loc.lat) * cos_d(eq.dec) * cos_d(h))
+}
+
+/// Shifts a geocentric position by the observer's parallax (lambana and nati
+/// together), for a body with horizontal parallax `parallax` degrees.
+pub(crate) fn topocentric(eq: Equatorial, parallax: f64, jd: f64, loc: &Location) -> Equatorial {
+    let h = hour_angle(eq, jd, loc);
+    let sp = sin_d(parallax);
+    let denom = cos_d(eq.dec) - cos_d(loc.lat) * sp * cos_d(h);
+    let d_ra = atan2_d(-cos_d(loc.lat) * sp * sin_d(h), denom);
+    let dec = atan2_d((sin_d(eq.dec) - sin_d(loc.lat) * sp) * cos_d(d_ra), denom);
+    Equatorial { ra: norm360(eq.ra + d_ra), dec }
+}
+
+/// Angular distance in degrees (haversine form, stable for small angles).
+pub(crate) fn separation(a: Equatorial, b: Equatorial) -> f64 {
+    let h = sin_d((a.dec - b.dec) / 2.0).powi(2)
+        + cos_d(a.dec) * cos_d(b.dec) * sin_d((a.ra - b.ra) / 2.0).powi(2);
+    2.0 * asin_d(h.sqrt().min(1.0))
+}
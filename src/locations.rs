@@ -0,0 +1,121 @@
+// =============================================================================
+// LOCATIONS: BUILT-IN CITY LIST AND USER-SUPPLIED FILES
+// =============================================================================
+
+use std::fs;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Location {
+    pub name: String,
+    pub lat: f64,          // Degrees, north positive
+    pub lon: f64,          // Degrees, east positive
+    pub tz: f64,           // Standard-time offset from UTC in hours (no DST)
+}
+
+// Name, latitude, longitude, UTC offset
+const BUILTIN: &[(&str, f64, f64, f64)] = &[
+    ("Ujjain", 23.1765, 75.7885, 5.5),
+    ("Delhi", 28.6139, 77.2090, 5.5),
+    ("Mumbai", 19.0760, 72.8777, 5.5),
+    ("Kolkata", 22.5726, 88.3639, 5.5),
+    ("Chennai", 13.0827, 80.2707, 5.5),
+    ("Bengaluru", 12.9716, 77.5946, 5.5),
+    ("Hyderabad", 17.3850, 78.4867, 5.5),
+    ("Ahmedabad", 23.0225, 72.5714, 5.5),
+    ("Varanasi", 25.3176, 82.9739, 5.5),
+    ("Thiruvananthapuram", 8.5241, 76.9366, 5.5),
+    ("Guwahati", 26.1445, 91.7362, 5.5),
+    ("Kathmandu", 27.7172, 85.3240, 5.75),
+    ("Colombo", 6.9271, 79.8612, 5.5),
+    ("Dhaka", 23.8103, 90.4125, 6.0),
+    ("Karachi", 24.8607, 67.0011, 5.0),
+    ("Singapore", 1.3521, 103.8198, 8.0),
+    ("Dubai", 25.2048, 55.2708, 4.0),
+    ("London", 51.5074, -0.1278, 0.0),
+    ("New York", 40.7128, -74.0060, -5.0),
+    ("Sydney", -33.8688, 151.2093, 10.0),
+];
+
+pub(crate) fn builtin() -> Vec<Location> {
+    BUILTIN.iter()
+        .map(|&(name, lat, lon, tz)| Location { name: name.to_string(), lat, lon, tz })
+        .collect()
+}
+
+/// Reads `Name|lat|lon|tz` lines; blank lines and `#` comments are skipped.
+pub(crate) fn load(path: &str) -> Result<Vec<Location>, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path, e))?;
+    parse(path, &raw)
+}
+
+fn parse(path: &str, raw: &str) -> Result<Vec<Location>, String> {
+    let mut out = Vec::new();
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let at = format!("{}:{}", path, i + 1);
+        let parts: Vec<&str> = line.split('|').map(str::trim).collect();
+        if parts.len() != 4 {
+            return Err(format!("{}: expected Name|lat|lon|tz", at));
+        }
+        if parts[0].is_empty() { return Err(format!("{}: empty name", at)); }
+        let num = |s: &str, what: &str, limit: f64| {
+            s.parse::<f64>().ok()
+                .filter(|v| v.is_finite() && v.abs() <= limit)
+                .ok_or_else(|| format!("{}: bad {} '{}' (must be within ±{})", at, what, s, limit))
+        };
+        out.push(Location {
+            name: parts[0].to_string(),
+            lat: num(parts[1], "latitude", 90.0)?,
+            lon: num(parts[2], "longitude", 180.0)?,
+            tz: num(parts[3], "UTC offset", 14.0)?,
+        });
+    }
+    if out.is_empty() { return Err(format!("{}: no locations", path)); }
+    Ok(out)
+}
+
+/// `--cities <FILE>` if given, otherwise the built-in list.
+pub(crate) fn from_args(args: &[String]) -> Result<Vec<Location>, String> {
+    match crate::flag_value(args, "--cities") {
+        Some(path) => load(path),
+        None => Ok(builtin()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines_and_skips_comments() {
+        let locs = parse("c.txt", "# cities\n\nUjjain | 23.1765 | 75.7885 | 5.5\nNew York|40.7|-74.0|-5\n").unwrap();
+        assert_eq!(locs.len(), 2);
+        assert_eq!(locs[0].name, "Ujjain");
+        assert_eq!(locs[1].lon, -74.0);
+        assert_eq!(locs[1].tz, -5.0);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert_eq!(parse("c.txt", "Ujjain|23|75\n").unwrap_err(), "c.txt:1: expected Name|lat|lon|tz");
+        assert!(parse("c.txt", "|23|75|5.5\n").unwrap_err().contains("empty name"));
+        assert!(parse("c.txt", "X|north|75|5.5\n").unwrap_err().contains("bad latitude"));
+        assert_eq!(parse("c.txt", "# nothing\n").unwrap_err(), "c.txt: no locations");
+    }
+
+    #[test]
+    fn rejects_non_finite_and_out_of_range_values() {
+        for line in ["X|23|75|inf", "X|nan|75|5.5", "X|23|75|1e300", "X|91|75|5.5", "X|23|-180.5|5.5", "X|23|75|-14.5"] {
+            let err = parse("c.txt", &format!("# header\n{}\n", line)).unwrap_err();
+            assert!(err.starts_with("c.txt:2: bad "), "{}: {}", line, err);
+        }
+    }
+
+    #[test]
+    fn builtin_list_is_valid() {
+        for loc in builtin() {
+            assert!(loc.lat.abs() <= 90.0 && loc.lon.abs() <= 180.0 && loc.tz.abs() <= 14.0, "{}", loc.name);
+        }
+    }
+}
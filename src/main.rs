@@ -2,15 +2,25 @@
 // SŪRYA SIDDHĀNTA: DYNAMIC APOGEE ENGINE
 // =============================================================================
 
-use chrono::{Datelike, NaiveDateTime, Timelike};
+mod bodies;
+mod daemon;
+mod eclipse;
+mod format;
+mod horizon;
+mod locations;
+mod panchanga;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike};
 use std::env;
 
 const MAHAYUGA_DAYS: f64 = 1_577_917_828.0;
 const JD_KALI_EPOCH: f64 = 588_465.50;
 const R: f64 = 3438.0;
 
+// Inferior and Node are only used by bodies loaded at runtime (see bodies.rs);
+// the built-in Mercury and Venus are recognised by name.
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum PlanetType { Luminary, Star }
+enum PlanetType { Luminary, Star, Inferior, Node }
 
 #[derive(Debug, Clone, Copy)]
 struct EpicycleDims { even: f64, odd: f64 }
@@ -90,12 +100,19 @@ const NODE_OFFSET: f64 = 189.47238376;
 // <<PLANET_DATA_END>>
 
 fn norm360(mut angle: f64) -> f64 {
-    angle = angle % 360.0;
+    angle %= 360.0;
     if angle < 0.0 { angle + 360.0 } else { angle }
 }
 fn sin_d(deg: f64) -> f64 { deg.to_radians().sin() }
 fn cos_d(deg: f64) -> f64 { deg.to_radians().cos() }
 fn asin_d(val: f64) -> f64 { val.asin().to_degrees() }
+fn atan2_d(y: f64, x: f64) -> f64 { y.atan2(x).to_degrees() }
+
+/// Normalises an angle into (-180, 180].
+fn wrap180(angle: f64) -> f64 {
+    let a = norm360(angle);
+    if a > 180.0 { a - 360.0 } else { a }
+}
 
 fn get_mean_longitude(days_elapsed: f64, revs: f64, correction: f64) -> f64 {
     let cycles = (days_elapsed * revs) / MAHAYUGA_DAYS;
@@ -123,12 +140,17 @@ fn get_sighra_correction(planet_lon: f64, sighrocca: f64, ep: EpicycleDims) -> f
     let kotiphala = r * cos_d(anomaly);
     let karna = ((R + kotiphala).powi(2) + dohphala.powi(2)).sqrt();
     let sine_val = (dohphala * R) / karna;
-    let clamped = sine_val.max(-R).min(R);
+    let clamped = sine_val.clamp(-R, R);
     asin_d(clamped / R)
 }
 
-fn calculate_true_position(days: f64, planet: &PlanetParam, sun_mean: f64) -> f64 {
-    let (mean_lon, sighrocca_lon) = match planet.ptype {
+/// Mean daily motion in degrees.
+fn daily_motion(revs: f64) -> f64 {
+    revs * 360.0 / MAHAYUGA_DAYS
+}
+
+fn get_mean_and_sighrocca(days: f64, planet: &PlanetParam, sun_mean: f64) -> (f64, f64) {
+    match planet.ptype {
         PlanetType::Luminary => (get_mean_longitude(days, planet.revs, planet.bija_offset), 0.0),
         PlanetType::Star => {
             if planet.name == "Mercury" || planet.name == "Venus" {
@@ -137,12 +159,21 @@ fn calculate_true_position(days: f64, planet: &PlanetParam, sun_mean: f64) -> f6
                 (get_mean_longitude(days, planet.revs, planet.bija_offset), sun_mean)
             }
         }
-    };
+        PlanetType::Inferior => (sun_mean, get_mean_longitude(days, planet.revs, planet.bija_offset)),
+        PlanetType::Node => (get_mean_longitude(days, planet.revs, planet.bija_offset), 0.0),
+    }
+}
+
+fn calculate_true_position(days: f64, planet: &PlanetParam, sun_mean: f64) -> f64 {
+    let (mean_lon, sighrocca_lon) = get_mean_and_sighrocca(days, planet, sun_mean);
+    if planet.ptype == PlanetType::Node {
+        return mean_lon;
+    }
     
     // Calculate Dynamic Apogee
     let manda_ucca = get_mean_longitude(days, planet.apsis_revs, planet.apsis_offset);
 
-    if planet.ptype == PlanetType::Luminary {
+    if planet.ptype == PlanetType::Luminary || planet.sighra_ep.is_none() {
         let corr = get_manda_correction(mean_lon, manda_ucca, planet.manda_ep);
         return norm360(mean_lon - corr);
     }
@@ -163,44 +194,123 @@ fn calculate_node_longitude(days: f64) -> f64 {
     norm360(motion) 
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let target_date_str = if args.len() > 1 { &args[1] } else { "2025-05-19T13:51:26" };
-    let dt = match NaiveDateTime::parse_from_str(target_date_str, "%Y-%m-%dT%H:%M:%S") {
-        Ok(d) => d,
-        Err(_) => { eprintln!("Error parsing date."); return; }
-    };
+fn get_mean_sun(days: f64) -> f64 {
+    let sun = &PLANETS[0];
+    get_mean_longitude(days, sun.revs, sun.bija_offset)
+}
 
+fn get_true_sun_moon(days: f64) -> (f64, f64) {
+    let mean_sun = get_mean_sun(days);
+    (calculate_true_position(days, &PLANETS[0], mean_sun), calculate_true_position(days, &PLANETS[1], mean_sun))
+}
+
+/// True longitudes of every body, nodes and user-defined bodies included.
+fn true_longitudes(days: f64) -> Vec<(&'static str, f64)> {
+    let mean_sun = get_mean_sun(days);
+    let mut out: Vec<(&'static str, f64)> = PLANETS.iter()
+        .map(|p| (p.name, calculate_true_position(days, p, mean_sun)))
+        .collect();
+    let rahu = calculate_node_longitude(days);
+    out.push(("Rahu", rahu));
+    out.push(("Ketu", norm360(rahu + 180.0)));
+    out.extend(bodies::custom().iter().map(|p| (p.name, calculate_true_position(days, p, mean_sun))));
+    out
+}
+
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+fn julian_day(dt: NaiveDateTime) -> f64 {
     let year = dt.year();
     let month = dt.month();
     let day = dt.day();
     let hour = dt.hour() as f64 + dt.minute() as f64 / 60.0 + dt.second() as f64 / 3600.0;
-    
+
     let a = (14 - month) / 12;
     let y = year + 4800 - a as i32;
-    let m = month + 12 * a as u32 - 3;
+    let m = month + 12 * a - 3;
     let jdn = day as i32 + (153 * m as i32 + 2) / 5 + 365 * y + y / 4 - y / 100 + y / 400 - 32045;
-    let jd = jdn as f64 + (hour - 12.0) / 24.0;
-    let days_elapsed = jd - JD_KALI_EPOCH;
+    jdn as f64 + (hour - 12.0) / 24.0
+}
+
+fn from_julian_day(jd: f64) -> NaiveDateTime {
+    let secs = ((jd - 2_440_587.5) * 86_400.0).round() as i64;
+    DateTime::from_timestamp(secs, 0).unwrap_or_default().naive_utc()
+}
+
+/// Value following `flag` on the command line, e.g. `--cities <FILE>`.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).map(|s| s.as_str())
+}
+
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|a| a == flag)
+}
+
+// Flags followed by a value, so that value is not mistaken for a positional
+const VALUE_FLAGS: &[&str] = &["--bodies", "--cities", "--kind", "--node", "--out-dir", "--days", "--interval"];
+
+/// Arguments after the program name that are neither flags nor flag values.
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(a) = iter.next() {
+        if VALUE_FLAGS.contains(&a.as_str()) {
+            iter.next();
+        } else if !a.starts_with("--") {
+            out.push(a.as_str());
+        }
+    }
+    out
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if has_flag(&args, "--bodies") {
+        let registered = match flag_value(&args, "--bodies").filter(|p| !p.starts_with("--")) {
+            Some(path) => bodies::register(path),
+            None => Err("--bodies needs <FILE>".to_string()),
+        };
+        if let Err(e) = registered {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if has_flag(&args, "--eclipse") {
+        if let Err(e) = eclipse::run(&args) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if has_flag(&args, "--daemon") {
+        if let Err(e) = daemon::run(&args) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let target_date_str = match positional_args(&args).as_slice() {
+        [] => "2025-05-19T13:51:26",
+        [date] => date,
+        [_, extra, ..] => { eprintln!("Error: unexpected argument '{}'.", extra); return; }
+    };
+    let dt = match parse_datetime(target_date_str) {
+        Some(d) => d,
+        None => { eprintln!("Error parsing date."); return; }
+    };
+
+    let days_elapsed = julian_day(dt) - JD_KALI_EPOCH;
 
     println!("Body|True|Mean|Sighra");
     
-    let sun_param = &PLANETS[0]; 
-    let mean_sun = get_mean_longitude(days_elapsed, sun_param.revs, sun_param.bija_offset);
+    let mean_sun = get_mean_sun(days_elapsed);
 
     for planet in PLANETS {
         let true_lon = calculate_true_position(days_elapsed, planet, mean_sun);
-        
-        let (disp_mean, disp_sighra) = match planet.ptype {
-            PlanetType::Luminary => (get_mean_longitude(days_elapsed, planet.revs, planet.bija_offset), 0.0),
-            PlanetType::Star => {
-                if planet.name == "Mercury" || planet.name == "Venus" {
-                    (mean_sun, get_mean_longitude(days_elapsed, planet.revs, planet.bija_offset))
-                } else {
-                    (get_mean_longitude(days_elapsed, planet.revs, planet.bija_offset), mean_sun)
-                }
-            }
-        };
+        let (disp_mean, disp_sighra) = get_mean_and_sighrocca(days_elapsed, planet, mean_sun);
         println!("{}|{:.6}|{:.6}|{:.6}", planet.name, true_lon, disp_mean, disp_sighra);
     }
     
@@ -208,4 +318,33 @@ fn main() {
     let ketu = norm360(rahu + 180.0);
     println!("Rahu|{:.6}|{:.6}|0.0", rahu, rahu);
     println!("Ketu|{:.6}|{:.6}|0.0", ketu, ketu);
-}
\ No newline at end of file
+
+    for planet in bodies::custom() {
+        let true_lon = calculate_true_position(days_elapsed, planet, mean_sun);
+        let (disp_mean, disp_sighra) = get_mean_and_sighrocca(days_elapsed, planet, mean_sun);
+        println!("{}|{:.6}|{:.6}|{:.6}", planet.name, true_lon, disp_mean, disp_sighra);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn positional_args_skip_flags_and_their_values() {
+        let a = args(&["surya_sidhanta", "--eclipse", "--cities", "c.txt", "2025-09-01T00:00:00"]);
+        assert_eq!(positional_args(&a), ["2025-09-01T00:00:00"]);
+        let a = args(&["surya_sidhanta", "--eclipse", "2025-09-01T00:00:00", "--kind", "lunar", "--json"]);
+        assert_eq!(positional_args(&a), ["2025-09-01T00:00:00"]);
+        assert!(positional_args(&args(&["surya_sidhanta", "--eclipse", "--kind", "solar"])).is_empty());
+        let a = args(&["surya_sidhanta", "2025-09-01T00:00:00", "extra"]);
+        assert_eq!(positional_args(&a), ["2025-09-01T00:00:00", "extra"]);
+        let a = args(&["surya_sidhanta", "--bodies", "b.conf", "2024-01-01T00:00:00"]);
+        assert_eq!(positional_args(&a), ["2024-01-01T00:00:00"]);
+        assert!(positional_args(&args(&["surya_sidhanta", "--bodies", "b.conf"])).is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+// =============================================================================
+// PANCHANGA: THE FIVE LIMBS AT LOCAL SUNRISE
+// =============================================================================
+
+use crate::horizon::{altitude, to_equatorial};
+use crate::locations::Location;
+use crate::{get_true_sun_moon, julian_day, norm360, true_longitudes, JD_KALI_EPOCH};
+use chrono::{Datelike, NaiveDate};
+
+const SUNRISE_ALT: f64 = -0.833;          // Upper limb on the horizon, with refraction
+const NAKSHATRA_ARC: f64 = 360.0 / 27.0;
+const SCAN_STEP: f64 = 1.0 / 48.0;        // Half an hour
+
+const VARAS: [&str; 7] = ["Ravivāra", "Somavāra", "Maṅgalavāra", "Budhavāra", "Guruvāra", "Śukravāra", "Śanivāra"];
+const TITHIS: [&str; 15] = [
+    "Pratipadā", "Dvitīyā", "Tṛtīyā", "Caturthī", "Pañcamī", "Ṣaṣṭhī", "Saptamī", "Aṣṭamī",
+    "Navamī", "Daśamī", "Ekādaśī", "Dvādaśī", "Trayodaśī", "Caturdaśī", "Pūrṇimā",
+];
+const NAKSHATRAS: [&str; 27] = [
+    "Aśvinī", "Bharaṇī", "Kṛttikā", "Rohiṇī", "Mṛgaśirā", "Ārdrā", "Punarvasu", "Puṣya", "Āśleṣā",
+    "Maghā", "Pūrva Phalgunī", "Uttara Phalgunī", "Hasta", "Citrā", "Svātī", "Viśākhā", "Anurādhā", "Jyeṣṭhā",
+    "Mūla", "Pūrva Āṣāḍhā", "Uttara Āṣāḍhā", "Śravaṇa", "Dhaniṣṭhā", "Śatabhiṣā", "Pūrva Bhādrapadā", "Uttara Bhādrapadā", "Revatī",
+];
+const YOGAS: [&str; 27] = [
+    "Viṣkambha", "Prīti", "Āyuṣmān", "Saubhāgya", "Śobhana", "Atigaṇḍa", "Sukarman", "Dhṛti", "Śūla",
+    "Gaṇḍa", "Vṛddhi", "Dhruva", "Vyāghāta", "Harṣaṇa", "Vajra", "Siddhi", "Vyatīpāta", "Varīyān",
+    "Parigha", "Śiva", "Siddha", "Sādhya", "Śubha", "Śukla", "Brahma", "Indra", "Vaidhṛti",
+];
+const MOVABLE_KARANAS: [&str; 7] = ["Bava", "Bālava", "Kaulava", "Taitila", "Gara", "Vaṇija", "Viṣṭi"];
+
+/// One limb: 1-based index within its cycle, name, and the Julian day (UTC) it ends.
+#[derive(Debug, Clone)]
+pub(crate) struct Element { pub index: usize, pub name: &'static str, pub ends: f64 }
+
+#[derive(Debug, Clone)]
+pub(crate) struct Panchanga {
+    pub date: NaiveDate,
+    pub vara: &'static str,
+    pub sunrise: Option<f64>,
+    pub sunset: Option<f64>,
+    pub paksha: &'static str,
+    pub tithi: Element,
+    pub nakshatra: Element,
+    pub yoga: Element,
+    pub karana: Element,
+    pub positions: Vec<(&'static str, f64)>,   // True longitudes at sunrise
+}
+
+fn sun_moon(jd: f64) -> (f64, f64) {
+    get_true_sun_moon(jd - JD_KALI_EPOCH)
+}
+
+fn tithi_at(jd: f64) -> usize {
+    let (sun, moon) = sun_moon(jd);
+    (norm360(moon - sun) / 12.0) as usize % 30
+}
+
+fn karana_at(jd: f64) -> usize {
+    let (sun, moon) = sun_moon(jd);
+    (norm360(moon - sun) / 6.0) as usize % 60
+}
+
+fn nakshatra_at(jd: f64) -> usize {
+    (sun_moon(jd).1 / NAKSHATRA_ARC) as usize % 27
+}
+
+fn yoga_at(jd: f64) -> usize {
+    let (sun, moon) = sun_moon(jd);
+    (norm360(sun + moon) / NAKSHATRA_ARC) as usize % 27
+}
+
+fn karana_name(k: usize) -> &'static str {
+    match k {
+        0 => "Kiṃstughna",
+        57 => "Śakuni",
+        58 => "Catuṣpada",
+        59 => "Nāga",
+        _ => MOVABLE_KARANAS[(k - 1) % 7],
+    }
+}
+
+/// First instant after `start` at which `f` changes value.
+fn ends<F: Fn(f64) -> usize>(f: F, start: f64) -> f64 {
+    let current = f(start);
+    let mut lo = start;
+    let mut hi = start + SCAN_STEP;
+    while f(hi) == current && hi - start < 3.0 {
+        lo = hi;
+        hi += SCAN_STEP;
+    }
+    for _ in 0..30 {
+        let mid = (lo + hi) / 2.0;
+        if f(mid) == current { lo = mid; } else { hi = mid; }
+    }
+    hi
+}
+
+fn sun_altitude(jd: f64, loc: &Location) -> f64 {
+    let (sun, _) = sun_moon(jd);
+    altitude(to_equatorial(sun, 0.0, jd), jd, loc) - SUNRISE_ALT
+}
+
+/// Crossing of the sunrise altitude within [from, from + 1 day), rising or setting.
+fn horizon_crossing(from: f64, loc: &Location, rising: bool) -> Option<f64> {
+    let steps = (1.0 / SCAN_STEP) as usize;
+    (0..steps).map(|i| from + i as f64 * SCAN_STEP).find_map(|lo| {
+        let hi = lo + SCAN_STEP;
+        let (a, b) = (sun_altitude(lo, loc), sun_altitude(hi, loc));
+        if (rising && a < 0.0 && b >= 0.0) || (!rising && a >= 0.0 && b < 0.0) {
+            let (mut lo, mut hi) = (lo, hi);
+            for _ in 0..30 {
+                let mid = (lo + hi) / 2.0;
+                if (sun_altitude(mid, loc) < 0.0) == (a < 0.0) { lo = mid; } else { hi = mid; }
+            }
+            Some((lo + hi) / 2.0)
+        } else {
+            None
+        }
+    })
+}
+
+/// Pañcāṅga for the civil `date` at `loc`, taken at local sunrise. Where the
+/// Sun does not rise (polar day or night) the limbs are taken at 06:00 local.
+pub(crate) fn compute(date: NaiveDate, loc: &Location) -> Panchanga {
+    let midnight = julian_day(date.and_hms_opt(0, 0, 0).unwrap_or_default()) - loc.tz / 24.0;
+    let sunrise = horizon_crossing(midnight, loc, true);
+    let sunset = horizon_crossing(sunrise.unwrap_or(midnight), loc, false);
+    let at = sunrise.unwrap_or(midnight + 0.25);
+
+    let t = tithi_at(at);
+    let n = nakshatra_at(at);
+    let y = yoga_at(at);
+    let k = karana_at(at);
+    Panchanga {
+        date,
+        vara: VARAS[date.weekday().num_days_from_sunday() as usize],
+        sunrise,
+        sunset,
+        paksha: if t < 15 { "Śukla" } else { "Kṛṣṇa" },
+        tithi: Element {
+            index: t + 1,
+            name: if t == 29 { "Amāvāsyā" } else { TITHIS[t % 15] },
+            ends: ends(tithi_at, at),
+        },
+        nakshatra: Element { index: n + 1, name: NAKSHATRAS[n], ends: ends(nakshatra_at, at) },
+        yoga: Element { index: y + 1, name: YOGAS[y], ends: ends(yoga_at, at) },
+        karana: Element { index: k + 1, name: karana_name(k), ends: ends(karana_at, at) },
+        positions: true_longitudes(at - JD_KALI_EPOCH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn karana_names_at_cycle_boundaries() {
+        assert_eq!(karana_name(0), "Kiṃstughna");
+        assert_eq!(karana_name(1), "Bava");
+        assert_eq!(karana_name(7), "Viṣṭi");
+        assert_eq!(karana_name(8), "Bava");
+        assert_eq!(karana_name(56), "Viṣṭi");
+        assert_eq!(karana_name(57), "Śakuni");
+        assert_eq!(karana_name(58), "Catuṣpada");
+        assert_eq!(karana_name(59), "Nāga");
+    }
+
+    #[test]
+    fn sunrise_precedes_sunset_at_ujjain() {
+        let loc = Location { name: "Ujjain".to_string(), lat: 23.1765, lon: 75.7885, tz: 5.5 };
+        let p = compute(NaiveDate::from_ymd_opt(2025, 5, 19).unwrap(), &loc);
+        let (rise, set) = (p.sunrise.unwrap(), p.sunset.unwrap());
+        assert!(set > rise && set - rise < 0.6);
+        assert!(p.tithi.ends > rise && p.karana.ends > rise);
+        assert_eq!(p.vara, "Somavāra");
+    }
+}